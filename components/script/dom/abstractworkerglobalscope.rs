@@ -2,6 +2,9 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::sync::OnceLock;
+use std::time;
+
 use crossbeam_channel::{select, Receiver, Sender};
 use devtools_traits::DevtoolScriptControlMsg;
 
@@ -84,6 +87,30 @@ impl ScriptPort for Receiver<DedicatedWorkerScriptMsg> {
     }
 }
 
+/// Details about an uncaught exception in a worker, forwarded to the worker's host so
+/// it can fire an `error` event on the owning `Worker`/`SharedWorker` object.
+#[derive(Clone)]
+pub struct WorkerErrorEvent {
+    pub message: String,
+    pub filename: String,
+    pub lineno: u32,
+    pub column: u32,
+}
+
+/// An event sent from a worker's event loop back to the entity that owns it, mirroring
+/// Deno's worker channel design. The `bool` that `handle_event` returns only tells
+/// `run_worker_event_loop` whether to keep pumping; it can't tell the host *why* the
+/// loop stopped. This channel carries that detail instead, so a recoverable uncaught
+/// exception (the loop keeps running) can be told apart from a runtime failure so severe
+/// that the worker cannot continue at all.
+pub enum WorkerHostEvent {
+    /// An uncaught exception occurred in the worker; the event loop continues running.
+    Error(WorkerErrorEvent),
+    /// The worker's JS runtime failed unrecoverably (e.g. out-of-memory, a forced
+    /// terminate); the event loop must unwind immediately.
+    TerminalError,
+}
+
 pub trait WorkerEventLoopMethods {
     type WorkerMsg: QueuedTaskConversion + Send;
     type ControlMsg;
@@ -96,6 +123,36 @@ pub trait WorkerEventLoopMethods {
     fn from_devtools_msg(msg: DevtoolScriptControlMsg) -> Self::Event;
     fn from_timer_msg() -> Self::Event;
     fn control_receiver(&self) -> &Receiver<Self::ControlMsg>;
+    /// An uncaught exception surfaced by the most recently handled event, if any.
+    /// Checked after every `handle_event` call regardless of its return value, so a
+    /// recoverable exception can be reported on [`Self::host_event_sender`] without
+    /// changing `handle_event`'s existing "keep running?" boolean contract. Defaults to
+    /// `None`, so implementors that don't report uncaught exceptions need no changes.
+    fn take_uncaught_exception(&self) -> Option<WorkerErrorEvent> {
+        None
+    }
+    /// Whether `handle_event` returning `false` reflects the worker's JS runtime having
+    /// failed unrecoverably, as opposed to an ordinary, expected close. Only consulted
+    /// when `handle_event` returns `false`. Defaults to `false` (an ordinary close), so
+    /// implementors that have no unrecoverable-failure case need no changes.
+    fn shutdown_is_terminal(&self) -> bool {
+        false
+    }
+    /// The channel on which host-bound events (uncaught exceptions, fatal runtime
+    /// failures) are sent back to the entity that owns this worker. Defaults to a
+    /// sender whose receiver has already been dropped, so implementors that don't wire
+    /// up a host channel silently discard these events instead of failing to compile.
+    fn host_event_sender(&self) -> &Sender<WorkerHostEvent> {
+        static DISCARDED: OnceLock<Sender<WorkerHostEvent>> = OnceLock::new();
+        DISCARDED.get_or_init(|| crossbeam_channel::unbounded().0)
+    }
+    /// If `msg` requests that the worker terminate, the grace period it should be given
+    /// to finish its current task and run cleanup before being forcibly aborted.
+    /// Defaults to `None` (no control message requests termination), so implementors
+    /// that don't have a terminate message need no changes.
+    fn as_terminate_msg(_msg: &Self::ControlMsg) -> Option<time::Duration> {
+        None
+    }
 }
 
 // https://html.spec.whatwg.org/multipage/#worker-event-loop
@@ -117,7 +174,14 @@ pub fn run_worker_event_loop<T, WorkerMsg, Event>(
     let devtools_receiver = scope.devtools_receiver().unwrap_or(&never);
 
     let event = select! {
-        recv(worker_scope.control_receiver()) -> msg => T::from_control_msg(msg.unwrap()),
+        recv(worker_scope.control_receiver()) -> msg => {
+            let msg = msg.unwrap();
+            if let Some(grace_period) = T::as_terminate_msg(&msg) {
+                run_termination_grace_period(worker_scope, worker, task_queue, grace_period, can_gc);
+                return;
+            }
+            T::from_control_msg(msg)
+        },
         recv(task_queue.select()) -> msg => {
             task_queue.take_tasks(msg.unwrap());
             T::from_worker_msg(task_queue.recv().unwrap())
@@ -150,8 +214,21 @@ pub fn run_worker_event_loop<T, WorkerMsg, Event>(
     // Step 3
     for event in sequential {
         let _realm = enter_realm(worker_scope);
-        if !worker_scope.handle_event(event, can_gc) {
-            // Shutdown
+        let should_continue = worker_scope.handle_event(event, can_gc);
+        if let Some(error) = worker_scope.take_uncaught_exception() {
+            let _ = worker_scope
+                .host_event_sender()
+                .send(WorkerHostEvent::Error(error));
+        }
+        if !should_continue {
+            if worker_scope.shutdown_is_terminal() {
+                let _ = worker_scope
+                    .host_event_sender()
+                    .send(WorkerHostEvent::TerminalError);
+            }
+            // Discard whatever is left on the task queue rather than running it,
+            // per the worker event loop's closing-flag semantics.
+            while task_queue.take_tasks_and_recv().is_ok() {}
             return;
         }
         // Step 6
@@ -167,3 +244,94 @@ pub fn run_worker_event_loop<T, WorkerMsg, Event>(
         .upcast::<GlobalScope>()
         .perform_a_dom_garbage_collection_checkpoint();
 }
+
+/// Give a worker a bounded grace period to finish its current task and run cleanup
+/// before forcibly aborting it, mirroring the wait-with-timeout semantics of an OS
+/// process being asked to exit. New tasks are no longer accepted once this runs (the
+/// caller only reaches here after receiving a terminate control message), and whatever
+/// is already queued is drained until either the queue runs dry or `grace_period`
+/// elapses, whichever comes first.
+fn run_termination_grace_period<T, WorkerMsg, Event>(
+    worker_scope: &T,
+    worker: Option<&TrustedWorkerAddress>,
+    task_queue: &TaskQueue<WorkerMsg>,
+    grace_period: time::Duration,
+    can_gc: CanGc,
+) where
+    WorkerMsg: QueuedTaskConversion + Send,
+    T: WorkerEventLoopMethods<WorkerMsg = WorkerMsg, Event = Event>
+        + DerivedFrom<WorkerGlobalScope>
+        + DerivedFrom<GlobalScope>
+        + DomObject,
+{
+    let scope = worker_scope.upcast::<WorkerGlobalScope>();
+    let deadline = time::Instant::now() + grace_period;
+    loop {
+        // The worker may already have closed itself before termination was requested;
+        // don't sit out the grace period just to force an abort that's no longer
+        // needed.
+        if scope.is_closing() {
+            return;
+        }
+        select! {
+            recv(crossbeam_channel::after(deadline.saturating_duration_since(time::Instant::now()))) -> _ => {
+                // The worker didn't finish up in time; the host has no choice but to
+                // abort it outright.
+                let _ = worker_scope
+                    .host_event_sender()
+                    .send(WorkerHostEvent::TerminalError);
+                return;
+            },
+            recv(task_queue.select()) -> msg => {
+                let Ok(msg) = msg else { return };
+                task_queue.take_tasks(msg);
+                while let Ok(task) = task_queue.recv() {
+                    // `select!` picks a ready arm at random rather than by declaration
+                    // order, so a steady stream of queued tasks could otherwise starve
+                    // the deadline arm above indefinitely. Check it explicitly on every
+                    // task instead of trusting we'll be scheduled back into the outer
+                    // `select!` in time.
+                    if scope.is_closing() {
+                        return;
+                    }
+                    if time::Instant::now() >= deadline {
+                        let _ = worker_scope
+                            .host_event_sender()
+                            .send(WorkerHostEvent::TerminalError);
+                        return;
+                    }
+                    let _realm = enter_realm(worker_scope);
+                    let should_continue = worker_scope.handle_event(T::from_worker_msg(task), can_gc);
+                    if let Some(error) = worker_scope.take_uncaught_exception() {
+                        let _ = worker_scope
+                            .host_event_sender()
+                            .send(WorkerHostEvent::Error(error));
+                    }
+                    if !should_continue {
+                        if worker_scope.shutdown_is_terminal() {
+                            let _ = worker_scope
+                                .host_event_sender()
+                                .send(WorkerHostEvent::TerminalError);
+                        }
+                        return;
+                    }
+                    // Step 6, same as the normal event loop's: keep cleanup semantics
+                    // consistent between a task run during the grace period and one run
+                    // outside of it.
+                    let _ar = match worker {
+                        Some(worker) => worker_scope.handle_worker_post_event(worker),
+                        None => None,
+                    };
+                    worker_scope
+                        .upcast::<GlobalScope>()
+                        .perform_a_microtask_checkpoint(can_gc);
+                }
+                // Everything that was queued when the grace period began has run; tasks
+                // posted since then are not accepted.
+                return;
+            },
+            // Nothing is queued: the worker has finished within its grace period.
+            default => return,
+        }
+    }
+}
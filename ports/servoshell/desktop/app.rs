@@ -0,0 +1,60 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! The embedder application driven by [`EventsLoop`](super::events_loop::EventsLoop).
+
+use std::time::Instant;
+
+use winit::application::ApplicationHandler;
+use winit::event::WindowEvent;
+use winit::event_loop::ActiveEventLoop;
+use winit::window::WindowId;
+
+use super::events_loop::WakerEvent;
+
+pub struct App {
+    animating: bool,
+}
+
+impl App {
+    pub fn init(&mut self, _window_id: Option<WindowId>) {}
+
+    /// Whether Servo currently has an in-progress animation (a running CSS animation,
+    /// requestAnimationFrame callback, etc.) that wants to keep ticking frames rather
+    /// than waiting indefinitely for external input.
+    pub fn is_animating(&self) -> bool {
+        self.animating
+    }
+
+    pub fn handle_events_with_headless(&mut self) -> bool {
+        false
+    }
+
+    /// The next time the headless event loop should wake up on its own to make
+    /// progress, without waiting for [`HeadlessEventLoopWaker::wake`](super::events_loop::HeadlessEventLoopWaker).
+    ///
+    /// Returns `None` when nothing has a concrete deadline yet; callers that are
+    /// animating should not treat `None` as "never wake up" (see
+    /// `EventsLoop::run_app`'s fallback), only as "no deadline is known right now".
+    pub fn next_deadline(&self) -> Option<Instant> {
+        None
+    }
+}
+
+impl ApplicationHandler<WakerEvent> for App {
+    fn resumed(&mut self, _event_loop: &ActiveEventLoop) {}
+
+    fn window_event(
+        &mut self,
+        _event_loop: &ActiveEventLoop,
+        _window_id: WindowId,
+        _event: WindowEvent,
+    ) {
+    }
+
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, _event: WakerEvent) {
+        // Nothing to do here directly: the wake's only job is to get `about_to_wait`
+        // to run again, where any side-channel draining this embedder needs happens.
+    }
+}
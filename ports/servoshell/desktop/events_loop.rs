@@ -7,18 +7,37 @@
 use std::cell::Cell;
 use std::sync::{Arc, Condvar, Mutex};
 use std::time;
+#[cfg(not(target_os = "macos"))]
+use std::thread;
 
+use crossbeam_channel::{Receiver, RecvError, Sender, TryRecvError};
 use log::warn;
 use servo::config::{pref, set_pref};
 use servo::embedder_traits::EventLoopWaker;
+use winit::application::ApplicationHandler;
 use winit::error::EventLoopError;
+use winit::event::WindowEvent;
 use winit::event_loop::{ActiveEventLoop, EventLoop as WinitEventLoop};
+#[cfg(not(target_os = "macos"))]
+use winit::platform::any_thread::EventLoopBuilderExtAny;
 #[cfg(target_os = "macos")]
 use winit::platform::macos::{ActivationPolicy, EventLoopBuilderExtMacOS};
+use winit::window::WindowId;
 
 use super::app::App;
 
+/// How often to wake up and tick a frame for an animating app that has no concrete
+/// next-frame deadline of its own. This only bounds the headless sleep so an animation
+/// keeps making progress; it does not drive frame timing itself.
+const ANIMATION_FRAME_FALLBACK_INTERVAL: time::Duration = time::Duration::from_millis(16);
+
 /// Another process or thread has kicked the OS event loop with EventLoopWaker.
+///
+/// This carries no payload: winit's `EventLoopProxy` has no wake primitive that is
+/// lighter than posting a user event, so a zero-sized marker is as close to a "pure"
+/// wake as the public API allows. Posting one pushes a single enum tag onto winit's
+/// internal queue — no heap allocation, no data to drain on the receiving end; the only
+/// purpose of the wake is to get `about_to_wait` to run again.
 #[derive(Debug)]
 pub struct WakerEvent;
 
@@ -85,8 +104,7 @@ impl EventsLoop {
         match self.0 {
             EventLoop::Winit(events_loop) => {
                 let events_loop = events_loop.expect("Can't run an unavailable event loop.");
-                events_loop
-                    .run_app(app)
+                Self::run_winit_app(events_loop, app)
                     .expect("Failed while running events loop");
             },
             EventLoop::Headless(ref data) => {
@@ -98,46 +116,206 @@ impl EventsLoop {
 
                 app.init(None);
                 loop {
-                    self.sleep(flag, condvar);
+                    // An idle app relies entirely on `HeadlessEventLoopWaker::wake` to break
+                    // the sleep, so it gets no deadline at all. An animating app always gets
+                    // one: its own `next_deadline`, if it has a concrete frame/timer to wake
+                    // up for, or else a fixed frame-paced fallback, so a running animation
+                    // keeps ticking instead of blocking indefinitely on the condvar.
+                    let deadline = app.is_animating().then(|| {
+                        app.next_deadline()
+                            .unwrap_or_else(|| time::Instant::now() + ANIMATION_FRAME_FALLBACK_INTERVAL)
+                    });
+                    self.sleep(flag, condvar, deadline);
+                    // Clear the flag *before* draining events, not after: a wake that
+                    // lands while `handle_events_with_headless` is running would
+                    // otherwise have its flag=true clobbered by a clear that runs after
+                    // the drain, leaving the next iteration to wait (possibly forever)
+                    // for an event that already arrived.
+                    *flag.lock().unwrap() = false;
                     if app.handle_events_with_headless() {
                         break;
                     }
-                    if !app.is_animating() {
-                        *flag.lock().unwrap() = false;
-                    }
                 }
             },
         }
     }
 
-    fn sleep(&self, lock: &Mutex<bool>, condvar: &Condvar) {
+    fn sleep(&self, lock: &Mutex<bool>, condvar: &Condvar, deadline: Option<time::Instant>) {
         // To avoid sleeping when we should be processing events, do two things:
         // * before sleeping, check whether our signalling flag has been set
-        // * wait on a condition variable with a maximum timeout, to allow
-        //   being woken up by any signals that occur while sleeping.
+        // * wait on a condition variable, to allow being woken up by any signals that
+        //   occur while sleeping.
         let guard = lock.lock().unwrap();
         if *guard {
             return;
         }
-        let _ = condvar
-            .wait_timeout(guard, time::Duration::from_millis(5))
-            .unwrap();
+        match deadline {
+            // There's a concrete deadline (an animation frame, a timer) to wake up for.
+            Some(deadline) => {
+                let timeout = deadline.saturating_duration_since(time::Instant::now());
+                let _ = condvar.wait_timeout(guard, timeout).unwrap();
+            },
+            // Nothing is scheduled: block until `wake` notifies us, instead of polling.
+            None => {
+                let _ = condvar.wait(guard).unwrap();
+            },
+        }
+    }
+
+    /// Run `app` on whichever winit event loop is already held by `self`/constructed by
+    /// [`Self::spawn`]. This is the one place that actually calls into winit's `run_app`,
+    /// so both the blocking [`Self::run_app`] convenience wrapper and the threaded
+    /// [`Self::spawn`] mechanism drive the loop the same way.
+    fn run_winit_app<A: ApplicationHandler<WakerEvent>>(
+        events_loop: winit::event_loop::EventLoop<WakerEvent>,
+        app: &mut A,
+    ) -> Result<(), EventLoopError> {
+        events_loop.run_app(app)
+    }
+
+    /// Start the real winit event loop on a dedicated thread and hand back a
+    /// [`EventsLoopHandle`], instead of handing this thread over to [`Self::run_app`] for
+    /// the lifetime of the program. This lets an embedder that owns its own control flow
+    /// poll Servo's windowing events synchronously and post its own events back in,
+    /// rather than having to express everything through an [`App`] callback.
+    ///
+    /// Not available on macOS: AppKit requires the event loop to be constructed and run
+    /// on the process's main thread, so there is no way to hand it off to a spawned one.
+    #[cfg(not(target_os = "macos"))]
+    pub fn spawn() -> Result<EventsLoopHandle, EventLoopError> {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        // The event loop itself is `!Send`, so it must be built on the thread that is
+        // going to run it; ship the waker back to the caller once it exists.
+        let (waker_sender, waker_receiver) = crossbeam_channel::bounded(1);
+        thread::Builder::new()
+            .name("ServoEventsLoop".to_owned())
+            .spawn(move || {
+                let mut builder = WinitEventLoop::<WakerEvent>::with_user_event();
+                builder.with_any_thread(true);
+                let events_loop = match builder.build() {
+                    Ok(events_loop) => events_loop,
+                    Err(err) => {
+                        warn!("Failed to build spawned events loop ({}).", err);
+                        return;
+                    },
+                };
+                let waker = HeadedEventLoopWaker::new(&events_loop);
+                if waker_sender
+                    .send(Box::new(waker) as Box<dyn EventLoopWaker>)
+                    .is_err()
+                {
+                    return;
+                }
+                let mut forwarder = EventForwarder { sender };
+                if let Err(err) = Self::run_winit_app(events_loop, &mut forwarder) {
+                    warn!("Failed while running spawned events loop ({}).", err);
+                }
+            })
+            .expect("Failed to spawn events loop thread");
+        let waker = waker_receiver
+            .recv()
+            .expect("Spawned events loop thread exited before building its event loop.");
+        Ok(EventsLoopHandle { receiver, waker })
+    }
+}
+
+/// A windowing event forwarded to an embedder driving Servo via [`EventsLoop::spawn`].
+#[derive(Debug)]
+pub enum SpawnedEvent {
+    Resumed,
+    WindowEvent {
+        window_id: WindowId,
+        event: WindowEvent,
+    },
+    AboutToWait,
+}
+
+/// A winit [`ApplicationHandler`] that, instead of acting on windowing events itself,
+/// simply relays them down a channel for an [`EventsLoopHandle`] to consume.
+///
+/// Only constructed by [`EventsLoop::spawn`], which is itself unavailable on macOS.
+#[cfg(not(target_os = "macos"))]
+struct EventForwarder {
+    sender: Sender<SpawnedEvent>,
+}
+
+#[cfg(not(target_os = "macos"))]
+impl ApplicationHandler<WakerEvent> for EventForwarder {
+    fn resumed(&mut self, _event_loop: &ActiveEventLoop) {
+        let _ = self.sender.send(SpawnedEvent::Resumed);
+    }
+
+    fn window_event(
+        &mut self,
+        _event_loop: &ActiveEventLoop,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        let _ = self
+            .sender
+            .send(SpawnedEvent::WindowEvent { window_id, event });
+    }
+
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, _event: WakerEvent) {
+        // Nothing to do: the waker's only job is to make sure this thread's event loop
+        // wakes up and calls `about_to_wait`, where we tell the embedder to drain its
+        // own side channel of queued messages.
+    }
+
+    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+        let _ = self.sender.send(SpawnedEvent::AboutToWait);
+    }
+}
+
+/// A handle to a winit event loop running on its own thread, returned by
+/// [`EventsLoop::spawn`]. An embedder drives its own control flow and calls
+/// [`Self::recv`]/[`Self::try_recv`] to consume Servo's windowing events, posting input
+/// or resize events back in through its own channels and waking the loop with
+/// [`Self::create_event_loop_waker`].
+pub struct EventsLoopHandle {
+    receiver: Receiver<SpawnedEvent>,
+    waker: Box<dyn EventLoopWaker>,
+}
+
+impl EventsLoopHandle {
+    /// Block until the next windowing event is available.
+    pub fn recv(&self) -> Result<SpawnedEvent, RecvError> {
+        self.receiver.recv()
+    }
+
+    /// Return the next windowing event without blocking, if one is already queued.
+    pub fn try_recv(&self) -> Result<SpawnedEvent, TryRecvError> {
+        self.receiver.try_recv()
+    }
+
+    /// A waker the embedder can use to kick the spawned event loop awake, e.g. after
+    /// posting a message on its own side channel.
+    pub fn create_event_loop_waker(&self) -> Box<dyn EventLoopWaker> {
+        self.waker.clone_box()
     }
 }
 
+// `winit::event_loop::EventLoopProxy` is already cheap to clone and safe to share across
+// threads, so unlike the headless waker below there is no shared state to synchronize here:
+// `wake` just kicks the OS event loop awake, carrying no payload of its own. Any data an
+// embedder wants delivered should go through its own channel, with `wake` called afterwards
+// to make sure the loop wakes up and drains it. `EventsLoop::spawn`'s `EventForwarder`
+// models that split already: it never interprets `WakerEvent`, it only relays windowing
+// events, leaving the embedder's own channel as the one place data flows through.
 struct HeadedEventLoopWaker {
-    proxy: Arc<Mutex<winit::event_loop::EventLoopProxy<WakerEvent>>>,
+    proxy: winit::event_loop::EventLoopProxy<WakerEvent>,
 }
 impl HeadedEventLoopWaker {
     fn new(events_loop: &winit::event_loop::EventLoop<WakerEvent>) -> HeadedEventLoopWaker {
-        let proxy = Arc::new(Mutex::new(events_loop.create_proxy()));
-        HeadedEventLoopWaker { proxy }
+        HeadedEventLoopWaker {
+            proxy: events_loop.create_proxy(),
+        }
     }
 }
 impl EventLoopWaker for HeadedEventLoopWaker {
     fn wake(&self) {
         // Kick the OS event loop awake.
-        if let Err(err) = self.proxy.lock().unwrap().send_event(WakerEvent) {
+        if let Err(err) = self.proxy.send_event(WakerEvent) {
             warn!("Failed to wake up event loop ({}).", err);
         }
     }